@@ -0,0 +1,392 @@
+use std::sync::{Condvar, Mutex};
+use std::task::Waker;
+use std::thread::{self, Thread};
+use std::time::Instant;
+
+use RecvTimeoutError;
+use SendTimeoutError;
+use TryRecvError;
+use TrySendError;
+use channel::Channel;
+
+struct Inner<T> {
+    // Fixed-size ring buffer. A slot is overwritten only once every live reader
+    // has advanced past it.
+    buf: Vec<Option<T>>,
+    // Monotonically increasing absolute index of the next write.
+    write_index: usize,
+    // Absolute read index for every registered receiver; `None` marks a slot
+    // vacated by a dropped receiver so it no longer holds back the sender.
+    cursors: Vec<Option<usize>>,
+    // Indices of vacated `cursors` slots, reused by the next subscriber so the
+    // vector does not grow without bound under subscriber churn.
+    free: Vec<usize>,
+    closed: bool,
+    // The single task parked via the `futures` integration, woken when a
+    // message lands or the channel closes. Replaced rather than accumulated on
+    // each registration.
+    waker: Option<Waker>,
+    // Threads parked through `subscribe` (used by `Select`).
+    threads: Vec<Thread>,
+}
+
+impl<T> Inner<T> {
+    fn wake_all(&mut self) {
+        if let Some(waker) = self.waker.take() {
+            waker.wake();
+        }
+        for thread in self.threads.iter() {
+            thread.unpark();
+        }
+    }
+
+    /// The index of the oldest slot still owned by some reader, or the write
+    /// index if there are no live readers.
+    fn min_cursor(&self) -> usize {
+        self.cursors
+            .iter()
+            .filter_map(|c| *c)
+            .min()
+            .unwrap_or(self.write_index)
+    }
+
+    fn has_readers(&self) -> bool {
+        self.cursors.iter().any(|c| c.is_some())
+    }
+}
+
+/// A fan-out channel: every live receiver observes every message.
+///
+/// The sender owns a single write index into a bounded ring buffer and each
+/// receiver keeps an independent read cursor. A slot may be reused only after
+/// the slowest reader has consumed it, so the sender blocks (or reports `Full`)
+/// when the buffer is a full `cap` ahead of the slowest cursor. Each reader
+/// clones its own copy of a message, so the element type must be `Clone`.
+pub struct Queue<T> {
+    cap: usize,
+    inner: Mutex<Inner<T>>,
+    not_full: Condvar,
+    not_empty: Condvar,
+}
+
+impl<T> Queue<T> {
+    pub fn with_capacity(cap: usize) -> Self {
+        let mut buf = Vec::with_capacity(cap);
+        for _ in 0..cap {
+            buf.push(None);
+        }
+        Queue {
+            cap: cap,
+            inner: Mutex::new(Inner {
+                buf: buf,
+                write_index: 0,
+                cursors: Vec::new(),
+                free: Vec::new(),
+                closed: false,
+                waker: None,
+                threads: Vec::new(),
+            }),
+            not_full: Condvar::new(),
+            not_empty: Condvar::new(),
+        }
+    }
+
+    /// Registers a fresh read cursor starting at the current write index, so a
+    /// late subscriber only sees messages sent from now on. Returns the cursor
+    /// handle passed back to `recv_at`/`try_recv_at`.
+    pub fn register_cursor(&self) -> usize {
+        let mut inner = self.inner.lock().unwrap();
+        let start = inner.write_index;
+        match inner.free.pop() {
+            Some(idx) => {
+                inner.cursors[idx] = Some(start);
+                idx
+            }
+            None => {
+                inner.cursors.push(Some(start));
+                inner.cursors.len() - 1
+            }
+        }
+    }
+
+    /// Removes a receiver's cursor so it can no longer hold back the sender and
+    /// returns its slot to the free-list for the next subscriber to reuse.
+    pub fn deregister(&self, cursor: usize) {
+        let mut inner = self.inner.lock().unwrap();
+        inner.cursors[cursor] = None;
+        inner.free.push(cursor);
+        self.not_full.notify_all();
+    }
+
+    /// A `Channel` view bound to a single receiver's cursor, so `Select` and the
+    /// `futures` integration can reach a broadcast receiver through the generic
+    /// interface with cursor-aware readiness and receive.
+    pub fn cursor(&self, cursor: usize) -> Cursor<T> {
+        Cursor {
+            queue: self,
+            cursor: cursor,
+        }
+    }
+
+    /// Whether the given cursor has an unread message (or the channel closed).
+    fn cursor_ready(&self, cursor: usize) -> bool {
+        let inner = self.inner.lock().unwrap();
+        if inner.closed {
+            return true;
+        }
+        match inner.cursors[cursor] {
+            Some(pos) => pos < inner.write_index,
+            None => false,
+        }
+    }
+
+    fn subscribe_thread(&self) {
+        self.inner.lock().unwrap().threads.push(thread::current());
+    }
+
+    fn unsubscribe_thread(&self) {
+        let id = thread::current().id();
+        self.inner.lock().unwrap().threads.retain(|t| t.id() != id);
+    }
+
+    fn register_waker_inner(&self, waker: &Waker) {
+        let mut inner = self.inner.lock().unwrap();
+        match inner.waker {
+            Some(ref w) if w.will_wake(waker) => {}
+            _ => inner.waker = Some(waker.clone()),
+        }
+    }
+}
+
+impl<T: Clone> Queue<T> {
+    pub fn try_recv_at(&self, cursor: usize) -> Result<T, TryRecvError> {
+        let mut inner = self.inner.lock().unwrap();
+        let pos = inner.cursors[cursor].expect("recv on a deregistered cursor");
+        if pos < inner.write_index {
+            let value = inner.buf[pos % self.cap].clone().unwrap();
+            inner.cursors[cursor] = Some(pos + 1);
+            self.not_full.notify_all();
+            Ok(value)
+        } else if inner.closed {
+            Err(TryRecvError::Disconnected)
+        } else {
+            Err(TryRecvError::Empty)
+        }
+    }
+
+    pub fn recv_at(&self, cursor: usize, deadline: Option<Instant>) -> Result<T, RecvTimeoutError> {
+        let mut inner = self.inner.lock().unwrap();
+        loop {
+            let pos = inner.cursors[cursor].expect("recv on a deregistered cursor");
+            if pos < inner.write_index {
+                let value = inner.buf[pos % self.cap].clone().unwrap();
+                inner.cursors[cursor] = Some(pos + 1);
+                self.not_full.notify_all();
+                return Ok(value);
+            }
+            if inner.closed {
+                return Err(RecvTimeoutError::Disconnected);
+            }
+            match deadline {
+                None => inner = self.not_empty.wait(inner).unwrap(),
+                Some(deadline) => {
+                    let now = Instant::now();
+                    if now >= deadline {
+                        return Err(RecvTimeoutError::Timeout);
+                    }
+                    let (guard, _) = self.not_empty.wait_timeout(inner, deadline - now).unwrap();
+                    inner = guard;
+                }
+            }
+        }
+    }
+}
+
+impl<T> Channel<T> for Queue<T> {
+    fn try_send(&self, value: T) -> Result<(), TrySendError<T>> {
+        // Hold `inner` across the whole check-and-write so two racing senders
+        // cannot both pass the capacity check and overrun the bound.
+        let mut inner = self.inner.lock().unwrap();
+        if inner.closed || !inner.has_readers() {
+            return Err(TrySendError::Disconnected(value));
+        }
+        if inner.write_index - inner.min_cursor() == self.cap {
+            return Err(TrySendError::Full(value));
+        }
+        let idx = inner.write_index % self.cap;
+        inner.buf[idx] = Some(value);
+        inner.write_index += 1;
+        inner.wake_all();
+        self.not_empty.notify_all();
+        Ok(())
+    }
+
+    fn send_until(&self, value: T, deadline: Option<Instant>) -> Result<(), SendTimeoutError<T>> {
+        let mut inner = self.inner.lock().unwrap();
+        loop {
+            if inner.closed || !inner.has_readers() {
+                return Err(SendTimeoutError::Disconnected(value));
+            }
+            if inner.write_index - inner.min_cursor() < self.cap {
+                let idx = inner.write_index % self.cap;
+                inner.buf[idx] = Some(value);
+                inner.write_index += 1;
+                inner.wake_all();
+                self.not_empty.notify_all();
+                return Ok(());
+            }
+            match deadline {
+                None => inner = self.not_full.wait(inner).unwrap(),
+                Some(deadline) => {
+                    let now = Instant::now();
+                    if now >= deadline {
+                        return Err(SendTimeoutError::Timeout(value));
+                    }
+                    let (guard, _) = self.not_full.wait_timeout(inner, deadline - now).unwrap();
+                    inner = guard;
+                }
+            }
+        }
+    }
+
+    // The queue-level receive is cursor-free and unused: broadcast receivers
+    // receive through the inherent `recv_at`/`try_recv_at`, and reach the
+    // generic interface for readiness through [`Cursor`] (via
+    // `Receiver::as_channel`).
+    fn try_recv(&self) -> Result<T, TryRecvError> {
+        unreachable!("broadcast receive is cursor-bound; use Queue::cursor")
+    }
+
+    fn recv_until(&self, _deadline: Option<Instant>) -> Result<T, RecvTimeoutError> {
+        unreachable!("broadcast receive is cursor-bound; use Queue::cursor")
+    }
+
+    fn len(&self) -> usize {
+        let inner = self.inner.lock().unwrap();
+        inner.write_index - inner.min_cursor()
+    }
+
+    fn is_empty(&self) -> usize {
+        (self.len() == 0) as usize
+    }
+
+    fn is_full(&self) -> usize {
+        (self.len() == self.cap) as usize
+    }
+
+    fn capacity(&self) -> Option<usize> {
+        Some(self.cap)
+    }
+
+    fn close(&self) -> bool {
+        let mut inner = self.inner.lock().unwrap();
+        if inner.closed {
+            return false;
+        }
+        inner.closed = true;
+        inner.wake_all();
+        self.not_empty.notify_all();
+        self.not_full.notify_all();
+        true
+    }
+
+    fn is_closed(&self) -> bool {
+        self.inner.lock().unwrap().closed
+    }
+
+    fn subscribe(&self) {
+        self.subscribe_thread();
+    }
+
+    fn unsubscribe(&self) {
+        self.unsubscribe_thread();
+    }
+
+    fn register_waker(&self, waker: &Waker) {
+        self.register_waker_inner(waker);
+    }
+
+    fn is_ready(&self) -> bool {
+        let inner = self.inner.lock().unwrap();
+        inner.closed || inner.write_index > inner.min_cursor()
+    }
+
+    fn id(&self) -> usize {
+        self as *const _ as usize
+    }
+}
+
+/// A `Channel` view of a broadcast queue bound to one receiver's cursor.
+///
+/// Readiness and receive are evaluated against that cursor; the send side and
+/// metadata forward to the shared queue.
+pub struct Cursor<'a, T: 'a> {
+    queue: &'a Queue<T>,
+    cursor: usize,
+}
+
+impl<'a, T> Channel<T> for Cursor<'a, T> {
+    fn try_send(&self, value: T) -> Result<(), TrySendError<T>> {
+        self.queue.try_send(value)
+    }
+
+    fn send_until(&self, value: T, deadline: Option<Instant>) -> Result<(), SendTimeoutError<T>> {
+        self.queue.send_until(value, deadline)
+    }
+
+    // Cursor-aware receive needs `T: Clone`, which this all-`T` view cannot
+    // promise; `Select` and the `futures` integration call back through
+    // `Receiver::try_recv`/`recv` (which carry the bound) instead. `as_channel`
+    // only ever uses this view for readiness and the waiter hooks below.
+    fn try_recv(&self) -> Result<T, TryRecvError> {
+        unreachable!("broadcast receive goes through Receiver::try_recv")
+    }
+
+    fn recv_until(&self, _deadline: Option<Instant>) -> Result<T, RecvTimeoutError> {
+        unreachable!("broadcast receive goes through Receiver::recv")
+    }
+
+    fn len(&self) -> usize {
+        self.queue.len()
+    }
+
+    fn is_empty(&self) -> usize {
+        self.queue.is_empty()
+    }
+
+    fn is_full(&self) -> usize {
+        self.queue.is_full()
+    }
+
+    fn capacity(&self) -> Option<usize> {
+        self.queue.capacity()
+    }
+
+    fn close(&self) -> bool {
+        self.queue.close()
+    }
+
+    fn is_closed(&self) -> bool {
+        self.queue.is_closed()
+    }
+
+    fn subscribe(&self) {
+        self.queue.subscribe_thread();
+    }
+
+    fn unsubscribe(&self) {
+        self.queue.unsubscribe_thread();
+    }
+
+    fn register_waker(&self, waker: &Waker) {
+        self.queue.register_waker_inner(waker);
+    }
+
+    fn is_ready(&self) -> bool {
+        self.queue.cursor_ready(self.cursor)
+    }
+
+    fn id(&self) -> usize {
+        self.queue.id()
+    }
+}
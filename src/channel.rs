@@ -1,3 +1,4 @@
+use std::cell::UnsafeCell;
 use std::collections::VecDeque;
 use std::ptr;
 use std::sync::Arc;
@@ -15,12 +16,12 @@ use SendTimeoutError;
 use TryRecvError;
 use TrySendError;
 use async;
+use broadcast;
 use monitor::Monitor;
+use oneshot;
 use sync;
 use zero;
 
-// TODO: iterators
-
 pub trait Channel<T> {
     fn try_send(&self, value: T) -> Result<(), TrySendError<T>>;
     fn send_until(&self, value: T, deadline: Option<Instant>) -> Result<(), SendTimeoutError<T>>;
@@ -41,6 +42,12 @@ pub trait Channel<T> {
     fn is_ready(&self) -> bool;
     fn id(&self) -> usize;
 
+    /// Registers a task `Waker` to be woken the next time this queue becomes
+    /// ready, alongside the `Thread` tokens used for blocking callers. The
+    /// default is a no-op; flavors that drive async tasks override it. Used by
+    /// the `futures` integration instead of parking a thread.
+    fn register_waker(&self, _waker: &::std::task::Waker) {}
+
     fn send(&self, value: T) -> Result<(), SendError<T>> {
         match self.send_until(value, None) {
             Ok(()) => Ok(()),
@@ -67,15 +74,54 @@ pub trait Channel<T> {
 }
 
 enum Flavor<T> {
+    Oneshot(oneshot::Queue<T>),
     Async(async::Queue<T>),
     Sync(sync::Queue<T>),
     Zero(zero::Queue<T>),
+    Broadcast(broadcast::Queue<T>),
 }
 
 struct Queue<T> {
     senders: AtomicUsize,
     receivers: AtomicUsize,
-    flavor: Flavor<T>,
+    // Wrapped in an `UnsafeCell` so a oneshot flavor can be promoted to the
+    // async flavor in place while senders and receivers keep their `Arc`. The
+    // swap is serialized through `upgrade_lock`.
+    flavor: UnsafeCell<Flavor<T>>,
+    upgrade_lock: Mutex<()>,
+}
+
+impl<T> Queue<T> {
+    fn new(flavor: Flavor<T>) -> Arc<Self> {
+        Arc::new(Queue {
+            senders: AtomicUsize::new(0),
+            receivers: AtomicUsize::new(0),
+            flavor: UnsafeCell::new(flavor),
+            upgrade_lock: Mutex::new(()),
+        })
+    }
+
+    fn flavor(&self) -> &Flavor<T> {
+        unsafe { &*self.flavor.get() }
+    }
+
+    /// Promotes a oneshot flavor to the async flavor, migrating any value that
+    /// is still parked in the oneshot slot so multi-producer semantics hold.
+    ///
+    /// A no-op for any other flavor, and safe to call concurrently: the
+    /// `upgrade_lock` ensures the swap happens exactly once.
+    fn upgrade(&self) {
+        let _guard = self.upgrade_lock.lock().unwrap();
+        let pending = match *self.flavor() {
+            Flavor::Oneshot(ref q) => q.take_pending(),
+            _ => return,
+        };
+        let async_q = async::Queue::new();
+        if let Some(value) = pending {
+            let _ = async_q.send(value);
+        }
+        unsafe { *self.flavor.get() = Flavor::Async(async_q); }
+    }
 }
 
 pub struct Sender<T>(Arc<Queue<T>>);
@@ -89,59 +135,104 @@ impl<T> Sender<T> {
         Sender(q)
     }
 
+    pub(crate) fn as_channel(&self) -> &Channel<T> {
+        match *self.0.flavor() {
+            Flavor::Oneshot(ref q) => q,
+            Flavor::Async(ref q) => q,
+            Flavor::Sync(ref q) => q,
+            Flavor::Zero(ref q) => q,
+            Flavor::Broadcast(ref q) => q,
+        }
+    }
+
     pub fn send(&self, value: T) -> Result<(), SendError<T>> {
-        match self.0.flavor {
+        match *self.0.flavor() {
+            Flavor::Oneshot(ref q) => match q.try_send(value) {
+                Ok(()) => Ok(()),
+                Err(TrySendError::Full(v)) => {
+                    self.0.upgrade();
+                    self.send(v)
+                }
+                Err(TrySendError::Disconnected(v)) => Err(SendError(v)),
+            },
             Flavor::Async(ref q) => q.send(value),
             Flavor::Sync(ref q) => q.send(value),
             Flavor::Zero(ref q) => q.send(value),
+            Flavor::Broadcast(ref q) => q.send(value),
         }
     }
 
     pub fn send_timeout(&self, value: T, dur: Duration) -> Result<(), SendTimeoutError<T>> {
-        match self.0.flavor {
+        match *self.0.flavor() {
+            Flavor::Oneshot(ref q) => match q.try_send(value) {
+                Ok(()) => Ok(()),
+                Err(TrySendError::Full(v)) => {
+                    self.0.upgrade();
+                    self.send_timeout(v, dur)
+                }
+                Err(TrySendError::Disconnected(v)) => Err(SendTimeoutError::Disconnected(v)),
+            },
             Flavor::Async(ref q) => q.send_timeout(value, dur),
             Flavor::Sync(ref q) => q.send_timeout(value, dur),
             Flavor::Zero(ref q) => q.send_timeout(value, dur),
+            Flavor::Broadcast(ref q) => q.send_timeout(value, dur),
         }
     }
 
     pub fn try_send(&self, value: T) -> Result<(), TrySendError<T>> {
-        match self.0.flavor {
+        match *self.0.flavor() {
+            Flavor::Oneshot(ref q) => match q.try_send(value) {
+                Ok(()) => Ok(()),
+                Err(TrySendError::Full(v)) => {
+                    self.0.upgrade();
+                    self.try_send(v)
+                }
+                Err(e) => Err(e),
+            },
             Flavor::Async(ref q) => q.try_send(value),
             Flavor::Sync(ref q) => q.try_send(value),
             Flavor::Zero(ref q) => q.try_send(value),
+            Flavor::Broadcast(ref q) => q.try_send(value),
         }
     }
 
     pub fn len(&self) -> usize {
-        match self.0.flavor {
+        match *self.0.flavor() {
+            Flavor::Oneshot(ref q) => q.len(),
             Flavor::Async(ref q) => q.len(),
             Flavor::Sync(ref q) => q.len(),
             Flavor::Zero(ref q) => q.len(),
+            Flavor::Broadcast(ref q) => q.len(),
         }
     }
 
     pub fn is_empty(&self) -> usize {
-        match self.0.flavor {
+        match *self.0.flavor() {
+            Flavor::Oneshot(ref q) => q.is_empty(),
             Flavor::Async(ref q) => q.is_empty(),
             Flavor::Sync(ref q) => q.is_empty(),
             Flavor::Zero(ref q) => q.is_empty(),
+            Flavor::Broadcast(ref q) => q.is_empty(),
         }
     }
 
     pub fn is_full(&self) -> usize {
-        match self.0.flavor {
+        match *self.0.flavor() {
+            Flavor::Oneshot(ref q) => q.is_full(),
             Flavor::Async(ref q) => q.is_full(),
             Flavor::Sync(ref q) => q.is_full(),
             Flavor::Zero(ref q) => q.is_full(),
+            Flavor::Broadcast(ref q) => q.is_full(),
         }
     }
 
     pub fn capacity(&self) -> Option<usize> {
-        match self.0.flavor {
+        match *self.0.flavor() {
+            Flavor::Oneshot(ref q) => q.capacity(),
             Flavor::Async(ref q) => q.capacity(),
             Flavor::Sync(ref q) => q.capacity(),
             Flavor::Zero(ref q) => q.capacity(),
+            Flavor::Broadcast(ref q) => q.capacity(),
         }
     }
 }
@@ -149,10 +240,12 @@ impl<T> Sender<T> {
 impl<T> Drop for Sender<T> {
     fn drop(&mut self) {
         if self.0.senders.fetch_sub(1, SeqCst) == 1 {
-            match self.0.flavor {
+            match *self.0.flavor() {
+                Flavor::Oneshot(ref q) => q.close(),
                 Flavor::Async(ref q) => q.close(),
                 Flavor::Sync(ref q) => q.close(),
                 Flavor::Zero(ref q) => q.close(),
+                Flavor::Broadcast(ref q) => q.close(),
             };
         }
     }
@@ -160,11 +253,15 @@ impl<T> Drop for Sender<T> {
 
 impl<T> Clone for Sender<T> {
     fn clone(&self) -> Self {
+        // A oneshot channel is single-producer; the first clone means the
+        // caller wants multi-producer semantics, so promote to the async
+        // flavor before handing out a second sender.
+        self.0.upgrade();
         Sender::new(self.0.clone())
     }
 }
 
-pub struct Receiver<T>(Arc<Queue<T>>);
+pub struct Receiver<T>(Arc<Queue<T>>, usize);
 
 unsafe impl<T: Send> Send for Receiver<T> {}
 unsafe impl<T: Send> Sync for Receiver<T> {}
@@ -172,81 +269,261 @@ unsafe impl<T: Send> Sync for Receiver<T> {}
 impl<T> Receiver<T> {
     fn new(q: Arc<Queue<T>>) -> Self {
         q.receivers.fetch_add(1, SeqCst);
-        Receiver(q)
+        // Broadcast receivers each own an independent cursor; other flavors
+        // ignore the field.
+        let cursor = match *q.flavor() {
+            Flavor::Broadcast(ref b) => b.register_cursor(),
+            _ => 0,
+        };
+        Receiver(q, cursor)
     }
 
-    pub(crate) fn as_channel(&self) -> &Channel<T> {
-        match self.0.flavor {
-            Flavor::Async(ref q) => q,
-            Flavor::Sync(ref q) => q,
-            Flavor::Zero(ref q) => unimplemented!(),
+    pub(crate) fn as_channel<'a>(&'a self) -> Box<Channel<T> + 'a>
+    where
+        T: 'a,
+    {
+        // A broadcast receiver needs a cursor-bound view so readiness reflects
+        // *this* subscriber's backlog; the other flavors are shared, so a plain
+        // forwarding wrapper suffices. Boxed because the broadcast arm yields a
+        // different concrete type than the rest.
+        match *self.0.flavor() {
+            Flavor::Oneshot(ref q) => Box::new(ChannelRef(q)),
+            Flavor::Async(ref q) => Box::new(ChannelRef(q)),
+            Flavor::Sync(ref q) => Box::new(ChannelRef(q)),
+            Flavor::Zero(ref q) => Box::new(ChannelRef(q)),
+            Flavor::Broadcast(ref q) => Box::new(q.cursor(self.1)),
         }
     }
 
     pub fn recv(&self) -> Result<T, RecvError> {
-        match self.0.flavor {
+        match *self.0.flavor() {
+            Flavor::Oneshot(ref q) => q.recv(),
             Flavor::Async(ref q) => q.recv(),
             Flavor::Sync(ref q) => q.recv(),
             Flavor::Zero(ref q) => q.recv(),
+            Flavor::Broadcast(ref q) => match q.recv_at(self.1, None) {
+                Ok(v) => Ok(v),
+                Err(_) => Err(RecvError),
+            },
         }
     }
 
     pub fn recv_timeout(&self, dur: Duration) -> Result<T, RecvTimeoutError> {
-        match self.0.flavor {
+        match *self.0.flavor() {
+            Flavor::Oneshot(ref q) => q.recv_timeout(dur),
             Flavor::Async(ref q) => q.recv_timeout(dur),
             Flavor::Sync(ref q) => q.recv_timeout(dur),
             Flavor::Zero(ref q) => q.recv_timeout(dur),
+            Flavor::Broadcast(ref q) => q.recv_at(self.1, Some(Instant::now() + dur)),
         }
     }
 
     pub fn try_recv(&self) -> Result<T, TryRecvError> {
-        match self.0.flavor {
+        match *self.0.flavor() {
+            Flavor::Oneshot(ref q) => q.try_recv(),
             Flavor::Async(ref q) => q.try_recv(),
             Flavor::Sync(ref q) => q.try_recv(),
             Flavor::Zero(ref q) => q.try_recv(),
+            Flavor::Broadcast(ref q) => q.try_recv_at(self.1),
         }
     }
 
+    pub fn iter(&self) -> Iter<T> {
+        Iter { rx: self }
+    }
+
+    pub fn try_iter(&self) -> TryIter<T> {
+        TryIter { rx: self }
+    }
+
     pub fn len(&self) -> usize {
-        match self.0.flavor {
+        match *self.0.flavor() {
+            Flavor::Oneshot(ref q) => q.len(),
             Flavor::Async(ref q) => q.len(),
             Flavor::Sync(ref q) => q.len(),
             Flavor::Zero(ref q) => q.len(),
+            Flavor::Broadcast(ref q) => q.len(),
         }
     }
 
     pub fn is_empty(&self) -> usize {
-        match self.0.flavor {
+        match *self.0.flavor() {
+            Flavor::Oneshot(ref q) => q.is_empty(),
             Flavor::Async(ref q) => q.is_empty(),
             Flavor::Sync(ref q) => q.is_empty(),
             Flavor::Zero(ref q) => q.is_empty(),
+            Flavor::Broadcast(ref q) => q.is_empty(),
         }
     }
 
     pub fn is_full(&self) -> usize {
-        match self.0.flavor {
+        match *self.0.flavor() {
+            Flavor::Oneshot(ref q) => q.is_full(),
             Flavor::Async(ref q) => q.is_full(),
             Flavor::Sync(ref q) => q.is_full(),
             Flavor::Zero(ref q) => q.is_full(),
+            Flavor::Broadcast(ref q) => q.is_full(),
         }
     }
 
     pub fn capacity(&self) -> Option<usize> {
-        match self.0.flavor {
+        match *self.0.flavor() {
+            Flavor::Oneshot(ref q) => q.capacity(),
             Flavor::Async(ref q) => q.capacity(),
             Flavor::Sync(ref q) => q.capacity(),
             Flavor::Zero(ref q) => q.capacity(),
+            Flavor::Broadcast(ref q) => q.capacity(),
         }
     }
 }
 
+// Borrows a flavor's `Channel` as a trait object so `Receiver::as_channel` can
+// return one boxed type across all non-broadcast flavors. Every method just
+// forwards to the borrowed queue.
+struct ChannelRef<'a, T: 'a>(&'a Channel<T>);
+
+impl<'a, T> Channel<T> for ChannelRef<'a, T> {
+    fn try_send(&self, value: T) -> Result<(), TrySendError<T>> {
+        self.0.try_send(value)
+    }
+
+    fn send_until(&self, value: T, deadline: Option<Instant>) -> Result<(), SendTimeoutError<T>> {
+        self.0.send_until(value, deadline)
+    }
+
+    fn try_recv(&self) -> Result<T, TryRecvError> {
+        self.0.try_recv()
+    }
+
+    fn recv_until(&self, deadline: Option<Instant>) -> Result<T, RecvTimeoutError> {
+        self.0.recv_until(deadline)
+    }
+
+    fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    fn is_empty(&self) -> usize {
+        self.0.is_empty()
+    }
+
+    fn is_full(&self) -> usize {
+        self.0.is_full()
+    }
+
+    fn capacity(&self) -> Option<usize> {
+        self.0.capacity()
+    }
+
+    fn close(&self) -> bool {
+        self.0.close()
+    }
+
+    fn is_closed(&self) -> bool {
+        self.0.is_closed()
+    }
+
+    fn subscribe(&self) {
+        self.0.subscribe()
+    }
+
+    fn unsubscribe(&self) {
+        self.0.unsubscribe()
+    }
+
+    fn register_waker(&self, waker: &::std::task::Waker) {
+        self.0.register_waker(waker)
+    }
+
+    fn is_ready(&self) -> bool {
+        self.0.is_ready()
+    }
+
+    fn id(&self) -> usize {
+        self.0.id()
+    }
+}
+
+/// A blocking iterator over messages in a channel.
+///
+/// Each step calls [`Receiver::recv`], so it blocks until a message arrives and
+/// yields `None` once the channel is closed and drained.
+pub struct Iter<'a, T: 'a> {
+    rx: &'a Receiver<T>,
+}
+
+impl<'a, T> Iterator for Iter<'a, T> {
+    type Item = T;
+
+    fn next(&mut self) -> Option<T> {
+        self.rx.recv().ok()
+    }
+}
+
+/// A non-blocking iterator over the currently buffered messages in a channel.
+///
+/// Each step calls [`Receiver::try_recv`] and stops as soon as the channel is
+/// empty, even if more messages may arrive later.
+pub struct TryIter<'a, T: 'a> {
+    rx: &'a Receiver<T>,
+}
+
+impl<'a, T> Iterator for TryIter<'a, T> {
+    type Item = T;
+
+    fn next(&mut self) -> Option<T> {
+        self.rx.try_recv().ok()
+    }
+}
+
+/// A blocking iterator that owns the receiver it drains.
+///
+/// Like [`Iter`], but created by [`Receiver::into_iter`] so it can be used in a
+/// `for` loop that consumes the channel.
+pub struct IntoIter<T> {
+    rx: Receiver<T>,
+}
+
+impl<T> Iterator for IntoIter<T> {
+    type Item = T;
+
+    fn next(&mut self) -> Option<T> {
+        self.rx.recv().ok()
+    }
+}
+
+impl<'a, T> IntoIterator for &'a Receiver<T> {
+    type Item = T;
+    type IntoIter = Iter<'a, T>;
+
+    fn into_iter(self) -> Iter<'a, T> {
+        self.iter()
+    }
+}
+
+impl<T> IntoIterator for Receiver<T> {
+    type Item = T;
+    type IntoIter = IntoIter<T>;
+
+    fn into_iter(self) -> IntoIter<T> {
+        IntoIter { rx: self }
+    }
+}
+
 impl<T> Drop for Receiver<T> {
     fn drop(&mut self) {
+        // Retire this receiver's broadcast cursor so it stops holding the
+        // sender back, regardless of whether other receivers remain.
+        if let Flavor::Broadcast(ref q) = *self.0.flavor() {
+            q.deregister(self.1);
+        }
         if self.0.receivers.fetch_sub(1, SeqCst) == 1 {
-            match self.0.flavor {
+            match *self.0.flavor() {
+                Flavor::Oneshot(ref q) => q.close(),
                 Flavor::Async(ref q) => q.close(),
                 Flavor::Sync(ref q) => q.close(),
                 Flavor::Zero(ref q) => q.close(),
+                Flavor::Broadcast(ref q) => q.close(),
             };
         }
     }
@@ -259,23 +536,196 @@ impl<T> Clone for Receiver<T> {
 }
 
 pub fn unbounded<T>() -> (Sender<T>, Receiver<T>) {
-    let q = Arc::new(Queue {
-        senders: AtomicUsize::new(0),
-        receivers: AtomicUsize::new(0),
-        flavor: Flavor::Async(async::Queue::new()),
-    });
+    // Start life as a oneshot and upgrade to the async flavor only once a
+    // second sender or a second message proves the channel is multi-shot.
+    let q = Queue::new(Flavor::Oneshot(oneshot::Queue::new()));
     (Sender::new(q.clone()), Receiver::new(q))
 }
 
 pub fn bounded<T>(size: usize) -> (Sender<T>, Receiver<T>) {
-    let q = Arc::new(Queue {
-        senders: AtomicUsize::new(0),
-        receivers: AtomicUsize::new(0),
-        flavor: if size == 0 {
-            Flavor::Zero(zero::Queue::new())
-        } else {
-            Flavor::Sync(sync::Queue::with_capacity(size))
-        },
-    });
+    let flavor = if size == 0 {
+        Flavor::Zero(zero::Queue::new())
+    } else {
+        Flavor::Sync(sync::Queue::with_capacity(size))
+    };
+    let q = Queue::new(flavor);
+    (Sender::new(q.clone()), Receiver::new(q))
+}
+
+/// Creates a bounded fan-out channel holding up to `cap` messages, where every
+/// live receiver observes every message sent.
+///
+/// Unlike [`bounded`], cloning the [`Receiver`] adds another independent
+/// subscriber rather than another work-stealing consumer; a fresh subscriber
+/// only sees messages sent after it was created.
+pub fn broadcast<T: Clone>(cap: usize) -> (Sender<T>, Receiver<T>) {
+    let q = Queue::new(Flavor::Broadcast(broadcast::Queue::with_capacity(cap)));
     (Sender::new(q.clone()), Receiver::new(q))
 }
+
+#[cfg(test)]
+mod tests {
+    use super::{bounded, broadcast, unbounded};
+    use std::thread;
+
+    #[test]
+    fn oneshot_send_recv() {
+        // A freshly created `unbounded()` starts in the oneshot flavor.
+        let (tx, rx) = unbounded();
+        tx.send(42).unwrap();
+        assert_eq!(rx.recv().unwrap(), 42);
+    }
+
+    #[test]
+    fn oneshot_upgrades_on_second_send() {
+        let (tx, rx) = unbounded();
+        tx.send(1).unwrap();
+        tx.send(2).unwrap(); // promotes oneshot -> async
+        assert_eq!(rx.recv().unwrap(), 1);
+        assert_eq!(rx.recv().unwrap(), 2);
+    }
+
+    #[test]
+    fn oneshot_blocks_until_sent() {
+        let (tx, rx) = unbounded();
+        let handle = thread::spawn(move || rx.recv().unwrap());
+        tx.send(7).unwrap();
+        assert_eq!(handle.join().unwrap(), 7);
+    }
+
+    #[cfg(feature = "futures")]
+    #[test]
+    fn stream_poll_next_yields_sent_value() {
+        use futures::stream::Stream;
+        use std::pin::Pin;
+        use std::ptr;
+        use std::task::{Context, Poll, RawWaker, RawWakerVTable, Waker};
+
+        fn noop_waker() -> Waker {
+            fn no_op(_: *const ()) {}
+            fn clone(_: *const ()) -> RawWaker {
+                RawWaker::new(ptr::null(), &VTABLE)
+            }
+            static VTABLE: RawWakerVTable = RawWakerVTable::new(clone, no_op, no_op, no_op);
+            unsafe { Waker::from_raw(RawWaker::new(ptr::null(), &VTABLE)) }
+        }
+
+        let (tx, rx) = unbounded::<i32>();
+        let mut rx = rx;
+        let waker = noop_waker();
+        let mut cx = Context::from_waker(&waker);
+
+        assert_eq!(Pin::new(&mut rx).poll_next(&mut cx), Poll::Pending);
+        tx.send(99).unwrap();
+        assert_eq!(Pin::new(&mut rx).poll_next(&mut cx), Poll::Ready(Some(99)));
+    }
+
+    #[test]
+    fn select_on_empty_channel_is_not_ready() {
+        use select::Select;
+        use std::time::{Duration, Instant};
+
+        // Keep the sender alive so the channel is open but empty.
+        let (_tx, rx) = bounded::<i32>(1);
+        let mut sel = Select::new();
+        sel.recv(&rx, |_| panic!("a receive fired on an empty channel"));
+        let deadline = Instant::now() + Duration::from_millis(50);
+        assert_eq!(sel.ready_deadline(deadline), None);
+    }
+
+    #[test]
+    fn select_fires_the_ready_case() {
+        use select::Select;
+
+        let (tx, rx) = bounded::<i32>(1);
+        tx.send(5).unwrap();
+        let mut got = None;
+        let mut sel = Select::new();
+        sel.recv(&rx, |v| got = Some(v.unwrap()));
+        assert_eq!(sel.select(), 0);
+        drop(sel); // release the closure's borrow of `got`
+        assert_eq!(got, Some(5));
+    }
+
+    #[test]
+    fn two_producers_race_into_bounded() {
+        // A cap of 1 forces the senders to contend on the bound; every message
+        // must still arrive exactly once.
+        let (tx, rx) = bounded::<i32>(1);
+        let tx2 = tx.clone();
+        let h1 = thread::spawn(move || {
+            for i in 0..100 {
+                tx.send(i).unwrap();
+            }
+        });
+        let h2 = thread::spawn(move || {
+            for i in 100..200 {
+                tx2.send(i).unwrap();
+            }
+        });
+
+        let mut seen = Vec::new();
+        for _ in 0..200 {
+            seen.push(rx.recv().unwrap());
+        }
+        h1.join().unwrap();
+        h2.join().unwrap();
+
+        seen.sort();
+        assert_eq!(seen, (0..200).collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn iter_drains_in_order_then_stops_on_close() {
+        let (tx, rx) = unbounded();
+        tx.send(1).unwrap();
+        tx.send(2).unwrap();
+        tx.send(3).unwrap();
+        drop(tx); // closes the channel, so the blocking iterator terminates
+        let got: Vec<_> = (&rx).into_iter().collect();
+        assert_eq!(got, vec![1, 2, 3]);
+    }
+
+    #[test]
+    fn try_iter_stops_at_empty_without_blocking() {
+        let (tx, rx) = unbounded();
+        tx.send(1).unwrap();
+        tx.send(2).unwrap();
+        // The sender is still alive, so a blocking iterator would hang here;
+        // try_iter must stop as soon as the buffer drains.
+        let got: Vec<_> = rx.try_iter().collect();
+        assert_eq!(got, vec![1, 2]);
+    }
+
+    #[test]
+    fn select_send_fires_when_there_is_room() {
+        use select::Select;
+
+        // An empty, open, non-full bounded channel: a send case must be ready
+        // immediately rather than hanging on the recv-oriented readiness hook.
+        let (tx, rx) = bounded::<i32>(1);
+        let mut done = false;
+        let mut sel = Select::new();
+        sel.send(&tx, 5, |r| {
+            r.unwrap();
+            done = true;
+        });
+        assert_eq!(sel.select(), 0);
+        drop(sel); // release the closure's borrow of `done`
+        assert!(done);
+        assert_eq!(rx.recv().unwrap(), 5);
+    }
+
+    #[test]
+    fn broadcast_fans_out_to_every_receiver() {
+        let (tx, rx1) = broadcast(4);
+        let rx2 = rx1.clone();
+        tx.send(1).unwrap();
+        tx.send(2).unwrap();
+        // Both subscribers see the full sequence.
+        assert_eq!(rx1.recv().unwrap(), 1);
+        assert_eq!(rx1.recv().unwrap(), 2);
+        assert_eq!(rx2.recv().unwrap(), 1);
+        assert_eq!(rx2.recv().unwrap(), 2);
+    }
+}
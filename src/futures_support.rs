@@ -0,0 +1,76 @@
+//! `futures` integration, enabled by the `futures` feature.
+//!
+//! Implements `Stream` for [`Receiver`] and `Sink` for [`Sender`] so the
+//! channels can be awaited and plugged into combinator chains without a
+//! dedicated blocking thread. Both poll methods work through the non-blocking
+//! `try_recv` / capacity queries and register the task's `Waker` through
+//! `Channel::register_waker` rather than parking a `Thread`; they do not use
+//! the `subscribe` / `is_ready` hooks.
+
+use std::pin::Pin;
+use std::task::{Context, Poll};
+
+use futures::sink::Sink;
+use futures::stream::Stream;
+// NOTE: this module is declared as `futures_support` in the crate root so it
+// does not clash with the `futures` extern crate it depends on.
+
+use Receiver;
+use Sender;
+use SendError;
+use TryRecvError;
+use TrySendError;
+
+impl<T> Stream for Receiver<T> {
+    type Item = T;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context) -> Poll<Option<T>> {
+        match self.try_recv() {
+            Ok(value) => Poll::Ready(Some(value)),
+            Err(TryRecvError::Disconnected) => Poll::Ready(None),
+            Err(TryRecvError::Empty) => {
+                // Register before the second check so an item arriving in the
+                // window between the two `try_recv`s still wakes us.
+                self.as_channel().register_waker(cx.waker());
+                match self.try_recv() {
+                    Ok(value) => Poll::Ready(Some(value)),
+                    Err(TryRecvError::Disconnected) => Poll::Ready(None),
+                    Err(TryRecvError::Empty) => Poll::Pending,
+                }
+            }
+        }
+    }
+}
+
+impl<T> Sink<T> for Sender<T> {
+    type Error = SendError<T>;
+
+    fn poll_ready(self: Pin<&mut Self>, cx: &mut Context) -> Poll<Result<(), Self::Error>> {
+        // Unbounded channels are always ready; bounded ones apply backpressure
+        // until a slot frees up.
+        if self.capacity().is_none() || self.is_full() == 0 {
+            return Poll::Ready(Ok(()));
+        }
+        self.as_channel().register_waker(cx.waker());
+        if self.is_full() == 0 {
+            Poll::Ready(Ok(()))
+        } else {
+            Poll::Pending
+        }
+    }
+
+    fn start_send(self: Pin<&mut Self>, item: T) -> Result<(), Self::Error> {
+        match self.try_send(item) {
+            Ok(()) => Ok(()),
+            Err(TrySendError::Full(v)) | Err(TrySendError::Disconnected(v)) => Err(SendError(v)),
+        }
+    }
+
+    fn poll_flush(self: Pin<&mut Self>, _cx: &mut Context) -> Poll<Result<(), Self::Error>> {
+        Poll::Ready(Ok(()))
+    }
+
+    fn poll_close(self: Pin<&mut Self>, _cx: &mut Context) -> Poll<Result<(), Self::Error>> {
+        Poll::Ready(Ok(()))
+    }
+}
@@ -0,0 +1,258 @@
+use std::cell::UnsafeCell;
+use std::sync::Mutex;
+use std::sync::atomic::{AtomicBool, AtomicUsize};
+use std::sync::atomic::Ordering::{Acquire, Release, SeqCst};
+use std::task::Waker;
+use std::thread::{self, Thread};
+use std::time::Instant;
+
+use RecvTimeoutError;
+use SendTimeoutError;
+use TryRecvError;
+use TrySendError;
+use channel::Channel;
+
+// The slot is `EMPTY` until a value is parked in it, at which point the state
+// becomes `DATA`. If a receiver blocks before a value arrives it boxes its own
+// `Thread` handle and stores the pointer as the state word, so the sender can
+// unpark it. `DISCONNECTED` is set when either end goes away.
+const EMPTY: usize = 0;
+const DATA: usize = 1;
+const DISCONNECTED: usize = 2;
+
+/// A channel specialized for sending exactly one value.
+///
+/// It keeps a single slot plus an atomic state word and performs at most one
+/// allocation (the boxed `Thread` token, only when a receiver has to block).
+/// The moment multi-producer semantics are needed — a second `send` or the
+/// first `Sender::clone` — the enclosing `Queue` upgrades this flavor to the
+/// async one; see `channel::Queue::upgrade`.
+pub struct Queue<T> {
+    state: AtomicUsize,
+    slot: UnsafeCell<Option<T>>,
+    // `true` once a value has been handed to this oneshot, so the channel layer
+    // knows a further `send` must upgrade rather than overwrite.
+    used: AtomicBool,
+    // Serializes the writer election and slot write against `take_pending`, so a
+    // losing sender (or an upgrade) never reads the slot while the winner is
+    // mid-write.
+    write_lock: Mutex<()>,
+    // Threads parked through `subscribe` (used by `Select`), unparked on every
+    // state change.
+    waiters: Mutex<Vec<Thread>>,
+    // The single task parked through the `futures` integration, woken when the
+    // value lands or the channel closes. A fresh registration replaces the
+    // previous one so repeated polling while pending cannot accumulate wakers.
+    waker: Mutex<Option<Waker>>,
+}
+
+impl<T> Queue<T> {
+    pub fn new() -> Self {
+        Queue {
+            state: AtomicUsize::new(EMPTY),
+            slot: UnsafeCell::new(None),
+            used: AtomicBool::new(false),
+            write_lock: Mutex::new(()),
+            waiters: Mutex::new(Vec::new()),
+            waker: Mutex::new(None),
+        }
+    }
+
+    /// Returns `true` if a value has already been pushed into the slot, meaning
+    /// the next `send` must promote the channel to the async flavor.
+    pub fn is_used(&self) -> bool {
+        self.used.load(Acquire)
+    }
+
+    /// Moves the pending value out of the slot, if any, so it can be migrated
+    /// into the async queue during an upgrade.
+    pub fn take_pending(&self) -> Option<T> {
+        let _write = self.write_lock.lock().unwrap();
+        unsafe { (*self.slot.get()).take() }
+    }
+
+    fn wake(&self, token: usize) {
+        if token != EMPTY && token != DATA && token != DISCONNECTED {
+            let thread = unsafe { Box::from_raw(token as *mut Thread) };
+            thread.unpark();
+        }
+    }
+
+    fn wake_waiters(&self) {
+        for thread in self.waiters.lock().unwrap().iter() {
+            thread.unpark();
+        }
+        if let Some(waker) = self.waker.lock().unwrap().take() {
+            waker.wake();
+        }
+    }
+}
+
+impl<T> Channel<T> for Queue<T> {
+    fn try_send(&self, value: T) -> Result<(), TrySendError<T>> {
+        // Elect the single writer and write the slot *before* publishing, all
+        // under `write_lock`. `close` takes the same lock, so the DATA swap
+        // below can never race a concurrent DISCONNECTED: we observe the closed
+        // state here and bail without ever transiently publishing DATA (which a
+        // racing receiver could otherwise steal, leaving us to panic on an empty
+        // slot). A loser that sees `used == true` likewise cannot read a
+        // half-written slot via `take_pending`.
+        let token;
+        {
+            let _write = self.write_lock.lock().unwrap();
+            if self.used.load(Acquire) {
+                return Err(TrySendError::Full(value));
+            }
+            if self.state.load(Acquire) == DISCONNECTED {
+                return Err(TrySendError::Disconnected(value));
+            }
+            unsafe {
+                *self.slot.get() = Some(value);
+            }
+            self.used.store(true, Release);
+            // Not DISCONNECTED (checked above under the lock): either EMPTY or a
+            // parked receiver's token, both safe to wake.
+            token = self.state.swap(DATA, SeqCst);
+        }
+        self.wake(token);
+        self.wake_waiters();
+        Ok(())
+    }
+
+    fn send_until(&self, value: T, _deadline: Option<Instant>) -> Result<(), SendTimeoutError<T>> {
+        // A oneshot never blocks the sender: either the single slot is free or
+        // the channel must be upgraded by the caller beforehand.
+        match self.try_send(value) {
+            Ok(()) => Ok(()),
+            Err(TrySendError::Full(v)) => Err(SendTimeoutError::Timeout(v)),
+            Err(TrySendError::Disconnected(v)) => Err(SendTimeoutError::Disconnected(v)),
+        }
+    }
+
+    fn try_recv(&self) -> Result<T, TryRecvError> {
+        match self.state.load(Acquire) {
+            DATA => {
+                self.state.store(EMPTY, SeqCst);
+                Ok(unsafe { (*self.slot.get()).take().unwrap() })
+            }
+            DISCONNECTED => {
+                if let Some(v) = self.take_pending() {
+                    Ok(v)
+                } else {
+                    Err(TryRecvError::Disconnected)
+                }
+            }
+            _ => Err(TryRecvError::Empty),
+        }
+    }
+
+    fn recv_until(&self, deadline: Option<Instant>) -> Result<T, RecvTimeoutError> {
+        loop {
+            match self.try_recv() {
+                Ok(v) => return Ok(v),
+                Err(TryRecvError::Disconnected) => return Err(RecvTimeoutError::Disconnected),
+                Err(TryRecvError::Empty) => {}
+            }
+
+            let token = Box::into_raw(Box::new(thread::current())) as usize;
+            if self.state.compare_and_swap(EMPTY, token, SeqCst) != EMPTY {
+                // A value (or disconnect) raced in; reclaim the token and retry.
+                unsafe { drop(Box::from_raw(token as *mut Thread)); }
+                continue;
+            }
+
+            // Park until the sender swaps our token out. A spurious wakeup
+            // leaves the token installed, so loop and park again rather than
+            // reinstalling (which would spin) or returning early.
+            loop {
+                match deadline {
+                    None => thread::park(),
+                    Some(deadline) => {
+                        let now = Instant::now();
+                        if now >= deadline {
+                            // Best-effort: clear our token if it is still installed.
+                            if self.state.compare_and_swap(token, EMPTY, SeqCst) == token {
+                                unsafe { drop(Box::from_raw(token as *mut Thread)); }
+                            }
+                            return Err(RecvTimeoutError::Timeout);
+                        }
+                        thread::park_timeout(deadline - now);
+                    }
+                }
+                if self.state.load(SeqCst) != token {
+                    // The sender consumed our token (value or disconnect posted);
+                    // go around the outer loop to read it.
+                    break;
+                }
+            }
+        }
+    }
+
+    fn len(&self) -> usize {
+        if self.state.load(Acquire) == DATA { 1 } else { 0 }
+    }
+
+    fn is_empty(&self) -> usize {
+        (self.state.load(Acquire) != DATA) as usize
+    }
+
+    fn is_full(&self) -> usize {
+        0
+    }
+
+    fn capacity(&self) -> Option<usize> {
+        None
+    }
+
+    fn close(&self) -> bool {
+        // Hold `write_lock` so the transition to DISCONNECTED is serialized
+        // against a concurrent `try_send`'s slot write and DATA swap.
+        let token = {
+            let _write = self.write_lock.lock().unwrap();
+            self.state.swap(DISCONNECTED, SeqCst)
+        };
+        if token == DISCONNECTED {
+            return false;
+        }
+        self.wake(token);
+        self.wake_waiters();
+        true
+    }
+
+    fn is_closed(&self) -> bool {
+        self.state.load(Acquire) == DISCONNECTED
+    }
+
+    fn subscribe(&self) {
+        self.waiters.lock().unwrap().push(thread::current());
+    }
+
+    fn unsubscribe(&self) {
+        let id = thread::current().id();
+        self.waiters.lock().unwrap().retain(|t| t.id() != id);
+    }
+
+    fn register_waker(&self, waker: &Waker) {
+        let mut slot = self.waker.lock().unwrap();
+        match *slot {
+            Some(ref w) if w.will_wake(waker) => {}
+            _ => *slot = Some(waker.clone()),
+        }
+    }
+
+    fn is_ready(&self) -> bool {
+        let state = self.state.load(Acquire);
+        state == DATA || state == DISCONNECTED
+    }
+
+    fn id(&self) -> usize {
+        self as *const _ as usize
+    }
+}
+
+impl<T> Drop for Queue<T> {
+    fn drop(&mut self) {
+        let token = *self.state.get_mut();
+        self.wake(token);
+    }
+}
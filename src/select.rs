@@ -0,0 +1,200 @@
+use std::thread;
+use std::time::Instant;
+
+use RecvError;
+use SendError;
+use TryRecvError;
+use TrySendError;
+use channel::Receiver;
+use channel::Sender;
+
+// A single registered operation. The channel's element type is erased behind
+// the closures so a `Select` can mix receivers and senders of different types;
+// the closures route through the `Channel` trait's `subscribe`/`unsubscribe`/
+// `is_ready` hooks.
+struct Case<'a> {
+    is_ready: Box<FnMut() -> bool + 'a>,
+    subscribe: Box<FnMut() + 'a>,
+    unsubscribe: Box<FnMut() + 'a>,
+    // Attempts the operation. Returns `true` if it actually completed (the
+    // callback ran), or `false` if the channel turned out not to be ready after
+    // all — e.g. another thread consumed the message between the readiness scan
+    // and the attempt — in which case `Select` keeps waiting.
+    run: Box<FnMut() -> bool + 'a>,
+}
+
+/// Waits for the first of several channel operations to become ready.
+///
+/// Register receivers with [`Select::recv`] and senders with [`Select::send`],
+/// then call [`Select::ready`] to block until some case can complete, or
+/// [`Select::select`] to additionally perform the operation. Both return the
+/// index of the case that fired, in registration order. Cases are polled in a
+/// rotating order so no channel can starve another.
+pub struct Select<'a> {
+    cases: Vec<Case<'a>>,
+    start: usize,
+}
+
+impl<'a> Select<'a> {
+    pub fn new() -> Self {
+        Select {
+            cases: Vec::new(),
+            start: 0,
+        }
+    }
+
+    /// Registers a receive on `rx`, invoking `f` with the received value (or
+    /// `RecvError` if the channel closed) when this case is selected. Returns
+    /// the case index.
+    pub fn recv<T, F>(&mut self, rx: &'a Receiver<T>, mut f: F) -> usize
+    where
+        T: 'a,
+        F: FnMut(Result<T, RecvError>) + 'a,
+    {
+        let index = self.cases.len();
+        self.cases.push(Case {
+            is_ready: Box::new(move || rx.as_channel().is_ready()),
+            subscribe: Box::new(move || rx.as_channel().subscribe()),
+            unsubscribe: Box::new(move || rx.as_channel().unsubscribe()),
+            run: Box::new(move || match rx.try_recv() {
+                Ok(value) => {
+                    f(Ok(value));
+                    true
+                }
+                Err(TryRecvError::Disconnected) => {
+                    f(Err(RecvError));
+                    true
+                }
+                // Raced: the message was taken between the scan and here.
+                Err(TryRecvError::Empty) => false,
+            }),
+        });
+        index
+    }
+
+    /// Registers a send of `value` on `tx`, invoking `f` with the outcome when
+    /// this case is selected. Returns the case index.
+    pub fn send<T, F>(&mut self, tx: &'a Sender<T>, value: T, mut f: F) -> usize
+    where
+        T: 'a,
+        F: FnMut(Result<(), SendError<T>>) + 'a,
+    {
+        let index = self.cases.len();
+        let mut value = Some(value);
+        self.cases.push(Case {
+            // `Channel::is_ready` is receive-oriented ("a message to read, or
+            // closed") for every flavor, which is never what a send wants. A
+            // send is ready when the channel has room or has closed (so the
+            // attempt can surface the disconnect), mirroring `Sink::poll_ready`.
+            is_ready: Box::new(move || {
+                let ch = tx.as_channel();
+                ch.is_closed() || ch.is_full() == 0
+            }),
+            subscribe: Box::new(move || tx.as_channel().subscribe()),
+            unsubscribe: Box::new(move || tx.as_channel().unsubscribe()),
+            run: Box::new(move || {
+                let v = match value.take() {
+                    Some(v) => v,
+                    // Already sent on an earlier attempt.
+                    None => return true,
+                };
+                match tx.try_send(v) {
+                    Ok(()) => {
+                        f(Ok(()));
+                        true
+                    }
+                    Err(TrySendError::Disconnected(v)) => {
+                        f(Err(SendError(v)));
+                        true
+                    }
+                    // Raced: the slot filled up again between the scan and here.
+                    Err(TrySendError::Full(v)) => {
+                        value = Some(v);
+                        false
+                    }
+                }
+            }),
+        });
+        index
+    }
+
+    /// Blocks until a registered case is ready and returns its index, without
+    /// performing the operation.
+    pub fn ready(&mut self) -> usize {
+        self.wait(None, false).unwrap()
+    }
+
+    /// Like [`Select::ready`], but gives up after `deadline`, returning the
+    /// ready case index or `None` on timeout.
+    pub fn ready_deadline(&mut self, deadline: Instant) -> Option<usize> {
+        self.wait(Some(deadline), false)
+    }
+
+    /// Blocks until a registered case is ready, performs its operation, and
+    /// returns its index.
+    pub fn select(&mut self) -> usize {
+        self.wait(None, true).unwrap()
+    }
+
+    /// Like [`Select::select`], but gives up after `deadline`, returning the
+    /// fired case index or `None` on timeout.
+    pub fn select_deadline(&mut self, deadline: Instant) -> Option<usize> {
+        self.wait(Some(deadline), true)
+    }
+
+    fn scan(&mut self) -> Option<usize> {
+        let n = self.cases.len();
+        for i in 0..n {
+            let idx = (self.start + i) % n;
+            if (self.cases[idx].is_ready)() {
+                self.start = (idx + 1) % n;
+                return Some(idx);
+            }
+        }
+        None
+    }
+
+    fn wait(&mut self, deadline: Option<Instant>, run: bool) -> Option<usize> {
+        loop {
+            if let Some(idx) = self.scan() {
+                if run {
+                    // If the op raced away between the scan and here, keep
+                    // waiting rather than reporting a spurious completion.
+                    if !(self.cases[idx].run)() {
+                        continue;
+                    }
+                }
+                return Some(idx);
+            }
+
+            // Subscribe the current thread to every channel, then re-check once
+            // so an op that became ready between the scan and the subscribe
+            // cannot be missed.
+            for case in &mut self.cases {
+                (case.subscribe)();
+            }
+            let ready = self.scan();
+
+            if ready.is_none() {
+                match deadline {
+                    None => thread::park(),
+                    Some(deadline) => {
+                        let now = Instant::now();
+                        if now >= deadline {
+                            for case in &mut self.cases {
+                                (case.unsubscribe)();
+                            }
+                            return None;
+                        }
+                        thread::park_timeout(deadline - now);
+                    }
+                }
+            }
+
+            for case in &mut self.cases {
+                (case.unsubscribe)();
+            }
+            // Re-loop: re-scan and, if `run`, perform the op on the winner.
+        }
+    }
+}
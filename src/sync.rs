@@ -0,0 +1,266 @@
+use std::ptr;
+use std::sync::atomic::{AtomicBool, AtomicPtr, AtomicUsize};
+use std::sync::atomic::Ordering::{Acquire, Release, SeqCst};
+use std::sync::{Condvar, Mutex};
+use std::task::Waker;
+use std::thread::{self, Thread};
+use std::time::Instant;
+
+use RecvTimeoutError;
+use SendTimeoutError;
+use TryRecvError;
+use TrySendError;
+use channel::Channel;
+
+struct Node<T> {
+    value: Option<T>,
+    next: AtomicPtr<Node<T>>,
+}
+
+impl<T> Node<T> {
+    fn new(value: Option<T>) -> *mut Node<T> {
+        Box::into_raw(Box::new(Node {
+            value: value,
+            next: AtomicPtr::new(ptr::null_mut()),
+        }))
+    }
+}
+
+/// A bounded queue built on the Michael–Scott two-lock algorithm.
+///
+/// The queue is a singly-linked list headed by a dummy node. Receivers take
+/// the `head` lock and senders take the `tail` lock, so a dequeue and an enqueue can
+/// proceed concurrently; only the `len` counter, an `AtomicUsize`, is shared
+/// between them and it is what enforces the capacity bound. Blocked senders and
+/// receivers wait on the `not_full` / `not_empty` condvars, and a thread that
+/// frees or fills a slot signals the other side.
+pub struct Queue<T> {
+    cap: usize,
+    len: AtomicUsize,
+    head: Mutex<*mut Node<T>>,
+    tail: Mutex<*mut Node<T>>,
+    closed: AtomicBool,
+    not_empty: Condvar,
+    not_full: Condvar,
+    // Threads registered through `subscribe` (used by `Select`), unparked on
+    // every state change.
+    waiters: Mutex<Vec<Thread>>,
+    // The single task parked through the `futures` integration, woken on every
+    // state change so a `poll_next`/`poll_ready` is re-driven. A fresh
+    // registration replaces the previous one rather than accumulating.
+    waker: Mutex<Option<Waker>>,
+}
+
+unsafe impl<T: Send> Send for Queue<T> {}
+unsafe impl<T: Send> Sync for Queue<T> {}
+
+impl<T> Queue<T> {
+    pub fn with_capacity(cap: usize) -> Self {
+        // The dummy node decouples the head and tail locks: they only ever
+        // alias transiently, never while both locks are held.
+        let dummy = Node::new(None);
+        Queue {
+            cap: cap,
+            len: AtomicUsize::new(0),
+            head: Mutex::new(dummy),
+            tail: Mutex::new(dummy),
+            closed: AtomicBool::new(false),
+            not_empty: Condvar::new(),
+            not_full: Condvar::new(),
+            waiters: Mutex::new(Vec::new()),
+            waker: Mutex::new(None),
+        }
+    }
+
+    fn wake_waiters(&self) {
+        for thread in self.waiters.lock().unwrap().iter() {
+            thread.unpark();
+        }
+        if let Some(waker) = self.waker.lock().unwrap().take() {
+            waker.wake();
+        }
+    }
+
+    fn enqueue(&self, tail: &mut *mut Node<T>, value: T) {
+        let node = Node::new(Some(value));
+        unsafe {
+            (**tail).next.store(node, Release);
+        }
+        *tail = node;
+        self.len.fetch_add(1, SeqCst);
+        self.not_empty.notify_all();
+        self.wake_waiters();
+    }
+
+    fn dequeue(&self, head: &mut *mut Node<T>) -> Option<T> {
+        let node = *head;
+        let next = unsafe { (*node).next.load(Acquire) };
+        if next.is_null() {
+            return None;
+        }
+        // `next` becomes the new dummy; its value is moved out and the old dummy
+        // is freed.
+        let value = unsafe { (*next).value.take() };
+        *head = next;
+        unsafe {
+            drop(Box::from_raw(node));
+        }
+        self.len.fetch_sub(1, SeqCst);
+        self.not_full.notify_all();
+        self.wake_waiters();
+        value
+    }
+}
+
+impl<T> Channel<T> for Queue<T> {
+    fn try_send(&self, value: T) -> Result<(), TrySendError<T>> {
+        // Take the `tail` lock before inspecting `len`, so the capacity check and the
+        // enqueue are atomic with respect to other senders; without it two
+        // senders could both observe `len < cap` and overrun the bound. Only
+        // senders touch the tail, so a single lock suffices here.
+        let mut tail = self.tail.lock().unwrap();
+        if self.closed.load(Acquire) {
+            return Err(TrySendError::Disconnected(value));
+        }
+        if self.len.load(SeqCst) >= self.cap {
+            return Err(TrySendError::Full(value));
+        }
+        self.enqueue(&mut tail, value);
+        Ok(())
+    }
+
+    fn send_until(&self, value: T, deadline: Option<Instant>) -> Result<(), SendTimeoutError<T>> {
+        let mut tail = self.tail.lock().unwrap();
+        loop {
+            if self.closed.load(Acquire) {
+                return Err(SendTimeoutError::Disconnected(value));
+            }
+            if self.len.load(SeqCst) < self.cap {
+                self.enqueue(&mut tail, value);
+                return Ok(());
+            }
+            match deadline {
+                None => tail = self.not_full.wait(tail).unwrap(),
+                Some(deadline) => {
+                    let now = Instant::now();
+                    if now >= deadline {
+                        return Err(SendTimeoutError::Timeout(value));
+                    }
+                    let (guard, _) = self.not_full.wait_timeout(tail, deadline - now).unwrap();
+                    tail = guard;
+                }
+            }
+        }
+    }
+
+    fn try_recv(&self) -> Result<T, TryRecvError> {
+        let mut head = self.head.lock().unwrap();
+        match self.dequeue(&mut head) {
+            Some(value) => Ok(value),
+            None => {
+                if self.closed.load(Acquire) {
+                    Err(TryRecvError::Disconnected)
+                } else {
+                    Err(TryRecvError::Empty)
+                }
+            }
+        }
+    }
+
+    fn recv_until(&self, deadline: Option<Instant>) -> Result<T, RecvTimeoutError> {
+        let mut head = self.head.lock().unwrap();
+        loop {
+            if let Some(value) = self.dequeue(&mut head) {
+                return Ok(value);
+            }
+            if self.closed.load(Acquire) {
+                return Err(RecvTimeoutError::Disconnected);
+            }
+            match deadline {
+                None => head = self.not_empty.wait(head).unwrap(),
+                Some(deadline) => {
+                    let now = Instant::now();
+                    if now >= deadline {
+                        return Err(RecvTimeoutError::Timeout);
+                    }
+                    let (guard, _) = self.not_empty.wait_timeout(head, deadline - now).unwrap();
+                    head = guard;
+                }
+            }
+        }
+    }
+
+    fn len(&self) -> usize {
+        self.len.load(SeqCst)
+    }
+
+    fn is_empty(&self) -> usize {
+        (self.len() == 0) as usize
+    }
+
+    fn is_full(&self) -> usize {
+        (self.len() == self.cap) as usize
+    }
+
+    fn capacity(&self) -> Option<usize> {
+        Some(self.cap)
+    }
+
+    fn close(&self) -> bool {
+        if self.closed.swap(true, SeqCst) {
+            return false;
+        }
+        // Pass through both locks so a thread that checked `closed` under one of
+        // them is already waiting on its condvar before the notify below.
+        drop(self.head.lock().unwrap());
+        drop(self.tail.lock().unwrap());
+        // Wake everyone so no sender or receiver is stranded on a closed queue.
+        self.not_empty.notify_all();
+        self.not_full.notify_all();
+        self.wake_waiters();
+        true
+    }
+
+    fn is_closed(&self) -> bool {
+        self.closed.load(Acquire)
+    }
+
+    fn subscribe(&self) {
+        self.waiters.lock().unwrap().push(thread::current());
+    }
+
+    fn unsubscribe(&self) {
+        let id = thread::current().id();
+        self.waiters.lock().unwrap().retain(|t| t.id() != id);
+    }
+
+    fn register_waker(&self, waker: &Waker) {
+        let mut slot = self.waker.lock().unwrap();
+        match *slot {
+            Some(ref w) if w.will_wake(waker) => {}
+            _ => *slot = Some(waker.clone()),
+        }
+    }
+
+    fn is_ready(&self) -> bool {
+        // Readiness mirrors the other flavors: a pending message or a closed
+        // queue lets a receive complete immediately. Free capacity is not
+        // counted, since it would make every non-full queue ready.
+        self.len() > 0 || self.closed.load(Acquire)
+    }
+
+    fn id(&self) -> usize {
+        self as *const _ as usize
+    }
+}
+
+impl<T> Drop for Queue<T> {
+    fn drop(&mut self) {
+        // Walk the list from the dummy head, freeing every remaining node.
+        let mut node = *self.head.get_mut().unwrap();
+        while !node.is_null() {
+            let boxed = unsafe { Box::from_raw(node) };
+            node = boxed.next.load(Acquire);
+        }
+    }
+}